@@ -3,6 +3,7 @@ use std::{ops::{Add, Sub}, fmt::Display};
 use bevy::prelude::Component;
 
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Component)]
 pub struct Coordinates {
     pub x: u16,
@@ -1,13 +1,37 @@
-use crate::components::Coordinates;
+use crate::{components::Coordinates, resources::BoardId};
 
 #[derive(Debug, Clone, Copy)]
-pub struct TileTriggerEvent(pub Coordinates);
+pub struct TileTriggerEvent(pub BoardId, pub Coordinates);
 
 #[derive(Debug, Copy, Clone)]
-pub struct BoardCompletedEvent;
+pub struct BoardCompletedEvent(pub BoardId);
 
 #[derive(Debug, Copy, Clone)]
-pub struct BombExplosionEvent;
+pub struct BombExplosionEvent(pub BoardId, pub Coordinates);
 
 #[derive(Debug, Copy, Clone)]
-pub struct TileMarkEvent(pub Coordinates);
\ No newline at end of file
+pub struct TileMarkEvent(pub BoardId, pub Coordinates);
+
+#[derive(Debug, Copy, Clone)]
+pub struct HintEvent(pub BoardId);
+
+#[derive(Debug, Copy, Clone)]
+pub struct UndoEvent(pub BoardId);
+
+#[derive(Debug, Copy, Clone)]
+pub struct TileUncoveredEvent(pub BoardId, pub Coordinates);
+
+#[derive(Debug, Copy, Clone)]
+pub struct TileFlaggedEvent {
+    pub board: BoardId,
+    pub coords: Coordinates,
+    pub flagged: bool,
+}
+
+/// Despawns every board and creates them again from the current options
+#[derive(Debug, Copy, Clone)]
+pub struct BoardResetEvent;
+
+/// Despawns the board with this id, leaving the other boards running
+#[derive(Debug, Copy, Clone)]
+pub struct BoardDespawnEvent(pub BoardId);
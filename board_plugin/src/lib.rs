@@ -1,13 +1,19 @@
+use bevy::app::{Events, ManualEventReader};
 use bevy::ecs::schedule::StateData;
+use bevy::ecs::system::{EntityCommands, SystemParam};
 use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
 use bevy::log;
 use bevy::utils::AHashExt;
 use bevy::utils::HashMap;
+use bevy::window::{WindowId, WindowResized};
 use components::Bomb;
 use components::BombNeighbor;
 use resources::BoardAssets;
 use resources::BoardOptions;
+use resources::GameTimer;
+use resources::SavedGame;
+use resources::TileMark;
 use resources::tile::Tile;
 use resources::tile_map::TileMap;
 
@@ -16,20 +22,32 @@ use bevy_inspector_egui::RegisterInspectable;
 
 use crate::bounds::Bounds2;
 use crate::components::Coordinates;
+#[cfg(feature = "debug")]
 use crate::components::Uncover;
 use crate::events::BoardCompletedEvent;
+use crate::events::BoardDespawnEvent;
+use crate::events::BoardResetEvent;
 use crate::events::BombExplosionEvent;
+use crate::events::HintEvent;
+use crate::events::TileFlaggedEvent;
 use crate::events::TileMarkEvent;
 use crate::events::TileTriggerEvent;
+use crate::events::TileUncoveredEvent;
+use crate::events::UndoEvent;
 use crate::resources::Board;
+use crate::resources::BoardId;
+use crate::resources::MultiBoardOptions;
 use crate::resources::BoardPosition;
 use crate::resources::TileSize;
+use crate::resources::paused::Paused;
 
 pub mod components;
 pub mod resources;
 mod bounds;
 mod systems;
-mod events;
+pub mod events;
+
+pub use systems::camera::{MIN_ZOOM, MAX_ZOOM, ZOOM_STEP, PIXELS_PER_LINE};
 
 pub struct BoardPlugin<T> {
     pub running_state: T,
@@ -38,20 +56,35 @@ pub struct BoardPlugin<T> {
 impl<T: StateData> Plugin for BoardPlugin<T> {
     fn build(&self, app: &mut App) {
         // When the running states comes into the stack we load a board
-        app.add_system_set(
+        app.init_resource::<Paused>()
+        .add_system_set(
             SystemSet::on_enter(self.running_state.clone()).with_system(Self::create_board),
         )
         // We handle input and trigger events only if the state is active
         .add_system_set(
             SystemSet::on_update(self.running_state.clone())
-                .with_system(systems::input::input_handling)
-                .with_system(systems::uncover::trigger_event_handler),
+                .with_system(Self::place_bombs)
+                .with_system(systems::uncover::trigger_event_handler)
+                .with_system(systems::timer::tick_timer)
+                .with_system(systems::hint::hint_handler)
+                .with_system(Self::undo_move),
         )
         // We handle uncovering even if the state is inactive
         .add_system_set(
             SystemSet::on_in_stack_update(self.running_state.clone())
-                .with_system(systems::uncover::uncover_tiles)
-                .with_system(systems::mark::mark_tiles), // We add our new mark system
+                .with_system(systems::uncover::uncover_tiles.label("uncover_tiles"))
+                .with_system(systems::uncover::reveal_bombs)
+                .with_system(systems::timer::stop_timer)
+                .with_system(systems::mark::mark_tiles) // We add our new mark system
+                .with_system(systems::mark::flag_remaining_bombs.after("uncover_tiles")),
+        )
+        // Boards are reset once the commands of the other board systems are applied,
+        // which may still target their tiles
+        .add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            SystemSet::on_update(self.running_state.clone())
+                .with_system(Self::despawn_board)
+                .with_system(Self::reset_board),
         )
         .add_system_set(
             SystemSet::on_exit(self.running_state.clone())
@@ -60,7 +93,25 @@ impl<T: StateData> Plugin for BoardPlugin<T> {
         .add_event::<TileTriggerEvent>()
         .add_event::<TileMarkEvent>()
         .add_event::<BombExplosionEvent>()
-        .add_event::<BoardCompletedEvent>();
+        .add_event::<BoardCompletedEvent>()
+        .add_event::<HintEvent>()
+        .add_event::<UndoEvent>()
+        .add_event::<TileUncoveredEvent>()
+        .add_event::<TileFlaggedEvent>()
+        .add_event::<BoardResetEvent>()
+        .add_event::<BoardDespawnEvent>();
+
+        // Without a window (headless apps, tests) these systems do nothing and the board
+        // is only driven through events
+        app.add_system_set(
+            SystemSet::on_update(self.running_state.clone())
+                .with_system(systems::input::input_handling)
+                .with_system(systems::input::touch_handling),
+        )
+        .add_system_set(
+            SystemSet::on_in_stack_update(self.running_state.clone())
+                .with_system(Self::resize_board),
+        );
 
         #[cfg(feature = "debug")]
         {
@@ -75,25 +126,140 @@ impl<T: StateData> Plugin for BoardPlugin<T> {
     }
 }
 
+/// Optional camera controls: the mouse wheel zooms and a middle mouse drag pans over the boards
+pub struct BoardCameraPlugin;
+
+impl Plugin for BoardCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(systems::camera::zoom_camera)
+            .add_system(systems::camera::pan_camera);
+
+        log::info!("Loaded Board Camera Plugin");
+    }
+}
+
+/// Tile entities and the mark sprites of their covers, used to undo a move
+#[derive(SystemParam)]
+struct BoardTiles<'w, 's> {
+    tiles: Query<'w, 's, (Entity, &'static Coordinates, &'static Sprite, &'static Parent)>,
+    children: Query<'w, 's, &'static Children>,
+}
+
 impl<T> BoardPlugin<T> {
     pub fn create_board(
         mut commands: Commands,
         board_assets: Res<BoardAssets>,
         board_options: Option<Res<BoardOptions>>,
+        multi_board_options: Option<Res<MultiBoardOptions>>,
         window: Option<Res<WindowDescriptor>>,
+        saved_game: Option<Res<SavedGame>>,
+    ) {
+        let window_size = window.map(|w| (w.width, w.height)).unwrap_or((1280.0, 720.0));
+        Self::spawn_boards(
+            &mut commands,
+            &board_assets,
+            board_options.as_deref(),
+            multi_board_options.as_deref(),
+            window_size,
+            saved_game.map(|s| s.clone()),
+        );
+    }
+
+    /// Despawns the boards and creates them again from the current options in the same run
+    fn reset_board(
+        mut commands: Commands,
+        boards: Query<(Entity, &Board)>,
+        board_assets: Res<BoardAssets>,
+        board_options: Option<Res<BoardOptions>>,
+        multi_board_options: Option<Res<MultiBoardOptions>>,
+        windows: Option<Res<Windows>>,
+        mut reset_evr: EventReader<BoardResetEvent>,
+    ) {
+        if reset_evr.iter().count() == 0 { return; }
+        log::info!("Resetting boards");
+        Self::despawn_boards(&mut commands, boards.iter());
+
+        // Adaptive boards match the current window, which may have been resized
+        let window_size = windows
+            .as_ref()
+            .and_then(|w| w.get_primary())
+            .map(|w| (w.width(), w.height()))
+            .unwrap_or((1280.0, 720.0));
+        // A reset always starts a new game, saved games are left for the next state enter
+        Self::spawn_boards(
+            &mut commands,
+            &board_assets,
+            board_options.as_deref(),
+            multi_board_options.as_deref(),
+            window_size,
+            None,
+        );
+    }
+
+    /// Spawns every board of the options
+    fn spawn_boards(
+        commands: &mut Commands,
+        board_assets: &BoardAssets,
+        board_options: Option<&BoardOptions>,
+        multi_board_options: Option<&MultiBoardOptions>,
+        window_size: (f32, f32),
+        mut saved_game: Option<SavedGame>,
+    ) {
+        let boards_options = match multi_board_options {
+            Some(o) => o.0.clone(),
+            None => vec![board_options.cloned().unwrap_or_default()]
+        };
+
+        let mut restored = false;
+        for (id, options) in boards_options.into_iter().enumerate() {
+            let id = BoardId(id as u16);
+            let saved = match saved_game.take() {
+                Some(saved) if saved.board == id => Some(saved),
+                other => {
+                    saved_game = other;
+                    None
+                }
+            };
+            restored |= saved.is_some();
+            Self::spawn_board(commands, board_assets, id, options, window_size, saved);
+        }
+        // A saved game is only restored once
+        if restored {
+            commands.remove_resource::<SavedGame>();
+        }
+    }
+
+    /// Spawns a board entity with its tiles, restoring a saved game if any
+    fn spawn_board(
+        commands: &mut Commands,
+        board_assets: &BoardAssets,
+        id: BoardId,
+        options: BoardOptions,
+        window_size: (f32, f32),
+        saved_game: Option<SavedGame>,
     ) {
-        let options = board_options
-            .map(|o| o.clone())
-            .unwrap_or_default();
+        if let Err(e) = options.validate() {
+            log::error!("Invalid board options, board {} was not created: {}", id, e);
+            return;
+        }
 
-        let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1);
-        tile_map.set_bombs(options.bomb_count);
+        let (tile_map, bombs_placed) = match &saved_game {
+            Some(saved) => (saved.tile_map.clone(), saved.bombs_placed),
+            None => {
+                let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1);
+                tile_map.set_seed(options.seed);
+                tile_map.set_connectivity(options.connectivity);
+                // With a safe start the bombs are placed on the first uncover
+                if !options.safe_start {
+                    tile_map.set_bombs(options.bomb_count);
+                }
+                (tile_map, !options.safe_start)
+            }
+        };
 
         let mut covered_tiles = 
             HashMap::with_capacity((tile_map.width() * tile_map.height()).into());
 
-        let mut safe_start = None;
-
         #[cfg(feature = "debug")]
         log::info!("{}", tile_map.console_output());
 
@@ -102,7 +268,9 @@ impl<T> BoardPlugin<T> {
             TileSize::Fixed(v) => v,
             TileSize::Adaptive { min, max } => 
                 Self::adaptive_tile_size(
-                    window, (min, max), (tile_map.width(), tile_map.height())
+                    window_size,
+                    (min, max),
+                    (tile_map.width(), tile_map.height())
                 )
         };
 
@@ -110,15 +278,9 @@ impl<T> BoardPlugin<T> {
             tile_map.width() as f32 * tile_size,
             tile_map.height() as f32 * tile_size,
         );
-        log::info!("board size: {}", board_size);
+        log::info!("board {} size: {}", id, board_size);
 
-        // We define the board anchor position (bottom left)
-        let board_position = match options.position {
-            BoardPosition::Centered { offset } => {
-                Vec3::new(-(board_size.x / 2.0), -(board_size.y / 2.0), 0.0) + offset
-            },
-            BoardPosition::Custom(p) => p
-        };
+        let board_position = Self::board_position(&options.position, board_size);
 
         let board_entity = commands.spawn()
                 .with_children(|parent| {
@@ -135,7 +297,7 @@ impl<T> BoardPlugin<T> {
                     })
                           .insert(Name::new("Background"));
                 })
-                .insert(Name::new("Board"))
+                .insert(Name::new(format!("Board {}", id)))
                 .insert(Transform::from_translation(board_position))
                 .insert(GlobalTransform::default())
                 .with_children(|parent| {
@@ -144,14 +306,53 @@ impl<T> BoardPlugin<T> {
                         &tile_map,
                         tile_size,
                         options.tile_padding,
-                        &board_assets,
+                        board_assets,
                         &mut covered_tiles,
-                        &mut safe_start
                     );
                 })
                 .id();
 
-        commands.insert_resource(Board {
+        // The tile map has no bombs yet on a safe start
+        let bomb_count = if bombs_placed {
+            tile_map.bomb_count()
+        } else {
+            options.bomb_count
+        };
+        let mut marked_tiles = Vec::new();
+        let mut questioned_tiles = Vec::new();
+        let mut completed = false;
+        let mut exploded = false;
+        // A new board always starts a new game
+        let mut timer = GameTimer::default();
+
+        // We restore the uncovered and marked tiles of a saved game
+        if let Some(saved) = saved_game {
+            log::info!("Restoring saved game on board {}", id);
+            for coords in saved.uncovered_tiles.iter() {
+                if let Some(entity) = covered_tiles.remove(coords) {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+            let size = Vec2::splat(tile_size - options.tile_padding);
+            for (coords, mark) in saved.marked_tiles.iter()
+                .map(|c| (c, TileMark::Flagged))
+                .chain(saved.questioned_tiles.iter().map(|c| (c, TileMark::Questioned)))
+            {
+                if let Some(entity) = covered_tiles.get(coords) {
+                    systems::mark::spawn_mark(commands, *entity, mark, board_assets, size);
+                }
+            }
+            marked_tiles = saved.marked_tiles;
+            questioned_tiles = saved.questioned_tiles;
+            // A finished game stays over
+            completed = saved.completed;
+            exploded = saved.exploded;
+            timer = GameTimer::restored(saved.elapsed, completed || exploded);
+        }
+
+        commands.entity(board_entity).insert(Board {
+            id,
+            options,
             tile_map,
             bounds: Bounds2 {
                 position: board_position.xy(),
@@ -160,12 +361,54 @@ impl<T> BoardPlugin<T> {
             tile_size,
             covered_tiles,
             entity: board_entity,
-            marked_tiles: Vec::new(),
+            marked_tiles,
+            questioned_tiles,
+            completed,
+            exploded,
+            bombs_placed,
+            bomb_count,
+            last_move: Default::default(),
+            timer,
         });
+    }
 
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover);
+    /// Places the bombs on the first uncover, keeping the triggered tile and its neighbors safe
+    fn place_bombs(
+        mut commands: Commands,
+        mut boards: Query<&mut Board>,
+        board_assets: Res<BoardAssets>,
+        mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+        tiles: Query<(Entity, &Coordinates, &Sprite, &Parent)>,
+        paused: Res<Paused>,
+    ) {
+        if paused.0 { return; }
+        for event in tile_trigger_evr.iter() {
+            let mut board = match boards.iter_mut().find(|b| b.id == event.0) {
+                Some(b) => b,
+                None => continue
+            };
+            if board.bombs_placed || board.tile_to_uncover(&event.1).is_none() { continue; }
+
+            let coords = event.1;
+            let mut exclude = vec![coords];
+            exclude.extend(board.tile_map.safe_square_at(coords));
+            let bomb_count = board.options.bomb_count;
+            board.tile_map.set_bombs_excluding(bomb_count, &exclude);
+            board.bombs_placed = true;
+            board.bomb_count = board.tile_map.bomb_count();
+
+            #[cfg(feature = "debug")]
+            log::info!("{}", board.tile_map.console_output());
+
+            for (entity, coords, sprite, parent) in tiles.iter() {
+                if parent.0 != board.entity { continue; }
+                // The content matches its tile, which keeps its spawn size when the board is rescaled
+                let size = sprite
+                    .custom_size
+                    .map(|s| s.x)
+                    .unwrap_or(board.tile_size - board.options.tile_padding);
+                let tile = &board.tile_map[coords.y as usize][coords.x as usize];
+                Self::spawn_tile_content(&mut commands.entity(entity), tile, size, &board_assets);
             }
         }
     }
@@ -177,7 +420,6 @@ impl<T> BoardPlugin<T> {
         padding: f32,
         board_assets: &BoardAssets,
         covered_tiles: &mut HashMap<Coordinates, Entity>,
-        safe_start_entity: &mut Option<Entity>,
     ) {
         // Tiles
         for (y, line) in tile_map.iter().enumerate() {
@@ -205,55 +447,152 @@ impl<T> BoardPlugin<T> {
 
                 // We add the cover sprites
                 cmd.with_children(|parent| {
-                    let entity = parent.spawn_bundle(SpriteBundle {
-                        sprite: Sprite {
-                            custom_size: Some(Vec2::splat(size - padding)),
-                            color: board_assets.covered_tile_material.color,
-                            ..Default::default()
-                        },
-                        transform: Transform::from_xyz(0.0, 0.0, 2.0),
-                        texture: board_assets.covered_tile_material.texture.clone(),
-                        ..Default::default()
-                    })
-                    .insert(Name::new("Tile Cover"))
-                    .id();
+                    let entity = Self::spawn_cover(parent, Vec2::splat(size - padding), board_assets);
                     covered_tiles.insert(Coordinates {
                         x: x as u16,
                         y: y as u16
                     }, entity);
-                    if safe_start_entity.is_none() && *tile == Tile::Empty {
-                        *safe_start_entity = Some(entity);
-                    }
                 });
 
-                match tile {
-                    // If the tile is a bomb we add the matching component and a sprite child
-                    Tile::Bomb => {
-                        cmd.insert(Bomb);
-                        cmd.with_children(|parent| {
-                            parent.spawn_bundle(SpriteBundle {
-                                sprite: Sprite {
-                                    custom_size: Some(Vec2::splat(size - padding)),
-                                    ..Default::default()
-                                },
-                                transform: Transform::from_xyz(0.0, 0.0, 1.0),
-                                texture: board_assets.bomb_material.texture.clone(),
-                                ..Default::default()
-                            });
-                        });
-                    },
-                    // If the tile is a bomb neighbour we add the matching component and a text child
-                    Tile::BombNeighbor(v) => {
-                        cmd.insert(BombNeighbor { count: *v });
-                        cmd.with_children(|parent| {
-                            parent.spawn_bundle(Self::bomb_count_text_bundle(
-                                *v,
-                                board_assets,
-                                size - padding
-                            ));
-                        });
+                Self::spawn_tile_content(&mut cmd, tile, size - padding, board_assets);
+            }
+        }
+    }
+
+    /// Spawns a tile cover sprite, returning its entity
+    fn spawn_cover(parent: &mut ChildBuilder, size: Vec2, board_assets: &BoardAssets) -> Entity {
+        parent.spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(size),
+                color: board_assets.covered_tile_material.color,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 2.0),
+            texture: board_assets.covered_tile_material.texture.clone(),
+            ..Default::default()
+        })
+        .insert(Name::new("Tile Cover"))
+        .id()
+    }
+
+    /// Adds the bomb or bomb neighbor components and sprites matching `tile` to a tile entity
+    fn spawn_tile_content(
+        cmd: &mut EntityCommands,
+        tile: &Tile,
+        size: f32,
+        board_assets: &BoardAssets,
+    ) {
+        match tile {
+            // If the tile is a bomb we add the matching component and a sprite child
+            Tile::Bomb => {
+                cmd.insert(Bomb);
+                cmd.with_children(|parent| {
+                    parent.spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::splat(size)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                        texture: board_assets.bomb_material.texture.clone(),
+                        ..Default::default()
+                    });
+                });
+            },
+            // If the tile is a bomb neighbour we add the matching component and a text child
+            Tile::BombNeighbor(v) => {
+                cmd.insert(BombNeighbor { count: *v });
+                cmd.with_children(|parent| {
+                    parent.spawn_bundle(Self::bomb_count_text_bundle(
+                        *v,
+                        board_assets,
+                        size
+                    ));
+                });
+            }
+            Tile::Empty => ()
+        }
+    }
+
+    /// Reverts the last move, covering its uncovered tiles again and restoring its mark
+    fn undo_move(
+        mut commands: Commands,
+        mut boards: Query<&mut Board>,
+        board_assets: Res<BoardAssets>,
+        mut undo_evr: EventReader<UndoEvent>,
+        mut flagged: EventWriter<TileFlaggedEvent>,
+        tiles: BoardTiles,
+        paused: Res<Paused>,
+    ) {
+        if paused.0 { return; }
+        for event in undo_evr.iter() {
+            let mut board = match boards.iter_mut().find(|b| b.id == event.0) {
+                Some(b) => b,
+                None => continue
+            };
+            if board.exploded || board.completed {
+                log::info!("Can't undo, the game of board {} is over", board.id);
+                continue;
+            }
+            Self::undo_board_move(&mut commands, &mut board, &board_assets, &tiles, &mut flagged);
+        }
+    }
+
+    /// Reverts the last move of `board`
+    fn undo_board_move(
+        commands: &mut Commands,
+        board: &mut Board,
+        board_assets: &BoardAssets,
+        tiles: &BoardTiles,
+        flagged: &mut EventWriter<TileFlaggedEvent>,
+    ) {
+        let last_move = std::mem::take(&mut board.last_move);
+        let uncovered_tiles: HashMap<Coordinates, TileMark> =
+            last_move.uncovered_tiles.into_iter().collect();
+        log::info!("Undoing last move ({} uncovered tiles)", uncovered_tiles.len());
+        // The board is mutated while iterating over its tiles
+        let board_entity = board.entity;
+        let board_tiles = tiles.tiles.iter().filter(|(_, _, _, parent)| parent.0 == board_entity);
+        for (entity, coords, sprite, _) in board_tiles {
+            let mark = match uncovered_tiles.get(coords) {
+                Some(m) => *m,
+                None => continue
+            };
+            // Covers have the same size as their tile
+            let size = sprite.custom_size.unwrap_or_else(|| Vec2::splat(board.tile_size));
+            let mut cover = None;
+            commands.entity(entity).with_children(|parent| {
+                cover = Some(Self::spawn_cover(parent, size, board_assets));
+            });
+            if let Some(cover) = cover {
+                board.covered_tiles.insert(*coords, cover);
+                board.try_set_mark(coords, mark);
+                systems::mark::spawn_mark(commands, cover, mark, board_assets, size);
+                if mark == TileMark::Flagged {
+                    flagged.send(TileFlaggedEvent { board: board.id, coords: *coords, flagged: true });
+                }
+            }
+        }
+
+        if let Some((coords, mark)) = last_move.mark {
+            let current = board.mark_at(&coords);
+            if let Some(entity) = board.try_set_mark(&coords, mark) {
+                if let Ok(children) = tiles.children.get(entity) {
+                    for child in children.iter() {
+                        commands.entity(*child).despawn_recursive();
                     }
-                    Tile::Empty => ()
+                }
+                let size = tiles.tiles
+                    .iter()
+                    .find(|(_, c, _, parent)| **c == coords && parent.0 == board_entity)
+                    .and_then(|(_, _, sprite, _)| sprite.custom_size)
+                    .unwrap_or_else(|| Vec2::splat(board.tile_size));
+                systems::mark::spawn_mark(commands, entity, mark, board_assets, size);
+                if (current == TileMark::Flagged) != (mark == TileMark::Flagged) {
+                    flagged.send(TileFlaggedEvent {
+                        board: board.id,
+                        coords,
+                        flagged: mark == TileMark::Flagged,
+                    });
                 }
             }
         }
@@ -261,16 +600,69 @@ impl<T> BoardPlugin<T> {
 
     /// Computes a tile size that matches the window according to the tile map size
     fn adaptive_tile_size(
-        window: Option<Res<WindowDescriptor>>,
+        (window_width, window_height): (f32, f32),
         (min, max): (f32, f32),
         (width, height): (u16, u16)
     ) -> f32 {
-        let max_width = window.as_ref().map(|w| w.as_ref().width).unwrap_or(1280.0) / width as f32;
-        let max_height = window.map(|w| w.height).unwrap_or(720.0) / height as f32;
+        let max_width = window_width / width as f32;
+        let max_height = window_height / height as f32;
 
         max_width.min(max_height).clamp(min, max)
     }
 
+    /// Computes the board anchor position (bottom left)
+    fn board_position(position: &BoardPosition, board_size: Vec2) -> Vec3 {
+        match position {
+            BoardPosition::Centered { offset } => {
+                Vec3::new(-(board_size.x / 2.0), -(board_size.y / 2.0), 0.0) + *offset
+            },
+            BoardPosition::Custom(p) => *p
+        }
+    }
+
+    /// Rescales the adaptive boards to match the resized primary window
+    fn resize_board(
+        mut boards: Query<(&mut Board, &mut Transform)>,
+        window_resized_events: Option<Res<Events<WindowResized>>>,
+        mut window_resized_evr: Local<ManualEventReader<WindowResized>>,
+    ) {
+        let window_resized_events = match window_resized_events {
+            Some(e) => e,
+            None => return
+        };
+        let window_size = match window_resized_evr
+            .iter(&window_resized_events)
+            .filter(|event| event.id == WindowId::primary())
+            .last()
+        {
+            Some(event) => (event.width, event.height),
+            None => return
+        };
+        for (mut board, mut transform) in boards.iter_mut() {
+            let (min, max) = match board.options.tile_size {
+                TileSize::Fixed(_) => continue,
+                TileSize::Adaptive { min, max } => (min, max)
+            };
+            let width = board.tile_map.width();
+            let height = board.tile_map.height();
+            let tile_size = Self::adaptive_tile_size(window_size, (min, max), (width, height));
+
+            // Tiles are spawned at the initial tile size, we scale the whole board from there
+            let scale = tile_size / (board.tile_size / transform.scale.x);
+            let board_size = Vec2::new(width as f32 * tile_size, height as f32 * tile_size);
+            let board_position = Self::board_position(&board.options.position, board_size);
+            transform.translation = board_position;
+            transform.scale = Vec3::new(scale, scale, 1.0);
+
+            log::info!("board {} resized to: {}", board.id, board_size);
+            board.tile_size = tile_size;
+            board.bounds = Bounds2 {
+                position: board_position.xy(),
+                size: board_size
+            };
+        }
+    }
+
     /// Generates the bomb counter text 2D Bundle for a given value
     fn bomb_count_text_bundle(count: u8, board_assets: &BoardAssets, size: f32) -> Text2dBundle {
         let color = board_assets.bomb_counter_color(count);
@@ -294,8 +686,26 @@ impl<T> BoardPlugin<T> {
         }
     }
 
-    fn cleanup_board(board: Res<Board>, mut commands: Commands) {
-        commands.entity(board.entity).despawn_recursive();
-        commands.remove_resource::<Board>();
+    fn cleanup_board(boards: Query<(Entity, &Board)>, mut commands: Commands) {
+        Self::despawn_boards(&mut commands, boards.iter());
+    }
+
+    /// Despawns a single board by id
+    fn despawn_board(
+        mut commands: Commands,
+        boards: Query<(Entity, &Board)>,
+        mut despawn_evr: EventReader<BoardDespawnEvent>,
+    ) {
+        for event in despawn_evr.iter() {
+            Self::despawn_boards(&mut commands, boards.iter().filter(|(_, b)| b.id == event.0));
+        }
+    }
+
+    /// Despawns the board entities along with their tiles
+    fn despawn_boards<'a>(commands: &mut Commands, boards: impl Iterator<Item = (Entity, &'a Board)>) {
+        for (entity, board) in boards {
+            log::info!("Despawning board {}", board.id);
+            commands.entity(entity).despawn_recursive();
+        }
     }
 }
\ No newline at end of file
@@ -1,27 +1,75 @@
+use std::fmt::Display;
+
 use bevy::{prelude::*, log};
 use bevy::utils::HashMap;
 
 use crate::bounds::Bounds2;
 use crate::TileMap;
 use crate::components::Coordinates;
+use crate::resources::{BoardOptions, GameTimer, SavedGame};
+
+/// Mark state of a covered tile, cycled through by marking it
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TileMark {
+    Unmarked,
+    Flagged,
+    Questioned,
+}
+
+/// Tiles changed by the last uncover or mark, used to undo it
+#[derive(Debug, Clone, Default)]
+pub struct LastMove {
+    /// Uncovered tiles, along with their mark before being uncovered
+    pub uncovered_tiles: Vec<(Coordinates, TileMark)>,
+    /// Marked tile, along with its previous mark
+    pub mark: Option<(Coordinates, TileMark)>,
+}
 
-#[derive(Debug)]
+/// Identifier of a board, boards are numbered in creation order starting from 0
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoardId(pub u16);
+
+impl Display for BoardId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Board component, stored on the board entity.
+///
+/// Boards are no longer a resource, `Res<Board>` users should query `&Board` and pick
+/// a board by its `BoardId`
+#[derive(Debug, Component)]
 pub struct Board {
+    pub id: BoardId,
+    pub options: BoardOptions,
     pub tile_map: TileMap,
     pub bounds: Bounds2,
     pub tile_size: f32,
     pub covered_tiles: HashMap<Coordinates, Entity>,
     pub entity: Entity,
     pub marked_tiles: Vec<Coordinates>,
+    pub questioned_tiles: Vec<Coordinates>,
+    pub completed: bool,
+    pub exploded: bool,
+    pub bombs_placed: bool,
+    pub bomb_count: u16,
+    pub last_move: LastMove,
+    /// Elapsed time of the game on this board
+    pub timer: GameTimer,
 }
 
 impl Board {
-    /// Translates a mouse position to board coordinates
+    /// Translates a mouse position to board coordinates, assuming a centered unscaled camera
     pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinates> {
         // Window to world space
         let window_size = Vec2::new(window.width(), window.height());
-        let position = position - window_size / 2.0;
+        self.world_position(position - window_size / 2.0)
+    }
 
+    /// Translates a world position to board coordinates
+    pub fn world_position(&self, position: Vec2) -> Option<Coordinates> {
         // Bounds check
         if !self.bounds.in_bounds(position) { return None; }
         // World space to board space
@@ -32,6 +80,51 @@ impl Board {
         })
     }
 
+    /// Is there a bomb at `coords`, always `false` before the bombs are placed
+    pub fn is_bomb(&self, coords: &Coordinates) -> bool {
+        self.tile_map.is_bomb_at(*coords)
+    }
+
+    /// Is the tile at `coords` still covered
+    pub fn is_covered(&self, coords: &Coordinates) -> bool {
+        self.covered_tiles.contains_key(coords)
+    }
+
+    /// Is the tile at `coords` flagged
+    pub fn is_marked(&self, coords: &Coordinates) -> bool {
+        self.marked_tiles.contains(coords)
+    }
+
+    /// Takes a snapshot of the game, which can be restored by inserting it as a resource
+    pub fn save(&self) -> SavedGame {
+        let uncovered_tiles = (0..self.tile_map.height())
+            .flat_map(|y| (0..self.tile_map.width()).map(move |x| Coordinates { x, y }))
+            .filter(|c| !self.covered_tiles.contains_key(c))
+            .collect();
+        SavedGame {
+            board: self.id,
+            tile_map: self.tile_map.clone(),
+            bombs_placed: self.bombs_placed,
+            uncovered_tiles,
+            marked_tiles: self.marked_tiles.clone(),
+            questioned_tiles: self.questioned_tiles.clone(),
+            completed: self.completed,
+            exploded: self.exploded,
+            elapsed: self.timer.elapsed(),
+        }
+    }
+
+    /// Retrieves the mark of the tile at `coords`
+    pub fn mark_at(&self, coords: &Coordinates) -> TileMark {
+        if self.marked_tiles.contains(coords) {
+            TileMark::Flagged
+        } else if self.questioned_tiles.contains(coords) {
+            TileMark::Questioned
+        } else {
+            TileMark::Unmarked
+        }
+    }
+
     /// Retrieves a covered tile entity
     pub fn tile_to_uncover(&self, coords: &Coordinates) -> Option<&Entity> {
         if self.marked_tiles.contains(coords) {
@@ -45,17 +138,42 @@ impl Board {
     pub fn try_uncover_tile(&mut self, coords: &Coordinates) -> Option<Entity> {
         if self.marked_tiles.contains(coords) {
             self.unmark_tile(coords).unwrap();
-            None
-        } else {
-            self.covered_tiles.remove(coords)
         }
+        self.questioned_tiles.retain(|c| c != coords);
+        self.covered_tiles.remove(coords)
     }
 
-    /// We retrieve the adjacent covered tile entities of `coord`
+    /// We retrieve the adjacent covered and unflagged tile entities of `coord`
     pub fn adjacent_covered_tiles(&self, coord: Coordinates) -> Vec<Entity> {
         self.tile_map
             .safe_square_at(coord)
-            .filter_map(|c| self.covered_tiles.get(&c))
+            .filter_map(|c| self.tile_to_uncover(&c))
+            .copied()
+            .collect()
+    }
+
+    /// Retrieves the covered tile entities to uncover when chording on `coords`.
+    ///
+    /// Chording only applies to an uncovered bomb neighbor whose adjacent marks match its bomb count,
+    /// in which case all of its unmarked covered neighbors are returned
+    pub fn tiles_to_chord(&self, coords: &Coordinates) -> Vec<Entity> {
+        if coords.x >= self.tile_map.width() || coords.y >= self.tile_map.height()
+            || self.covered_tiles.contains_key(coords)
+            || self.tile_map.is_bomb_at(*coords)
+        {
+            return Vec::new();
+        }
+        let count = self.tile_map.bomb_count_at(*coords) as usize;
+        let marks = self.tile_map
+            .safe_square_at(*coords)
+            .filter(|c| self.marked_tiles.contains(c))
+            .count();
+        if count == 0 || count != marks {
+            return Vec::new();
+        }
+        self.tile_map
+            .safe_square_at(*coords)
+            .filter_map(|c| self.tile_to_uncover(&c))
             .copied()
             .collect()
     }
@@ -72,20 +190,43 @@ impl Board {
         Some(self.marked_tiles.remove(pos))
     }
 
-    /// Is the board complete
+    /// Is the board complete, i.e. every remaining covered tile is a bomb
     pub fn is_completed(&self) -> bool {
         self.tile_map.bomb_count() as usize == self.covered_tiles.len()
+            && self.covered_tiles.keys().all(|c| self.tile_map.is_bomb_at(*c))
+    }
+
+    /// Number of bombs left to flag, negative if the player placed too many flags
+    pub fn remaining_bombs(&self) -> i32 {
+        self.bomb_count as i32 - self.marked_tiles.len() as i32
+    }
+
+    /// We try to set the mark of a covered tile, returning its entity
+    pub fn try_set_mark(&mut self, coords: &Coordinates, mark: TileMark) -> Option<Entity> {
+        let entity = *self.covered_tiles.get(coords)?;
+        self.marked_tiles.retain(|c| c != coords);
+        self.questioned_tiles.retain(|c| c != coords);
+        match mark {
+            TileMark::Flagged => self.marked_tiles.push(*coords),
+            TileMark::Questioned => self.questioned_tiles.push(*coords),
+            TileMark::Unmarked => ()
+        }
+        Some(entity)
     }
 
-    /// We try to mark or unmark a tile, returning the entity and if the tile is marked
-    pub fn try_toggle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, bool)> {
+    /// We try to cycle the mark of a tile (unmarked, flagged, questioned), returning the entity and its new mark
+    pub fn try_cycle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, TileMark)> {
         let entity = *self.covered_tiles.get(coords)?;
         let mark = if self.marked_tiles.contains(coords) {
             self.unmark_tile(coords)?;
-            false
+            self.questioned_tiles.push(*coords);
+            TileMark::Questioned
+        } else if self.questioned_tiles.contains(coords) {
+            self.questioned_tiles.retain(|c| c != coords);
+            TileMark::Unmarked
         } else {
             self.marked_tiles.push(*coords);
-            true
+            TileMark::Flagged
         };
         Some((entity, mark))
     }
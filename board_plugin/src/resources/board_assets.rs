@@ -37,6 +37,10 @@ pub struct BoardAssets {
     ///
     pub flag_material: SpriteMaterial,
     ///
+    pub question_material: SpriteMaterial,
+    /// Color of an optional `?` drawn with the bomb counter font over `question_material`
+    pub question_mark_color: Option<Color>,
+    ///
     pub bomb_material: SpriteMaterial,
 }
 
@@ -1,3 +1,5 @@
+use std::{error::Error, fmt::{self, Display}};
+
 use bevy::prelude::Vec3;
 use serde::{Serialize, Deserialize};
 
@@ -25,6 +27,21 @@ impl Default for BoardPosition {
     }
 }
 
+/// Which tiles are adjacent to each other, for bomb counts and uncovering
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Connectivity {
+    /// Orthogonal neighbors only
+    FourWay,
+    /// Orthogonal and diagonal neighbors
+    EightWay
+}
+
+impl Default for Connectivity {
+    fn default() -> Self {
+        Self::EightWay
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BoardOptions {
     pub map_size: (u16, u16),
@@ -33,7 +50,9 @@ pub struct BoardOptions {
     pub tile_size: TileSize,
     pub tile_padding: f32,
     pub safe_start: bool,
-    pub restart: bool
+    pub restart: bool,
+    pub seed: Option<u64>,
+    pub connectivity: Connectivity
 }
 
 impl Default for BoardOptions {
@@ -45,7 +64,89 @@ impl Default for BoardOptions {
             tile_size: Default::default(),
             tile_padding: 0.0,
             safe_start: false,
-            restart: false
+            restart: false,
+            seed: None,
+            connectivity: Default::default()
+        }
+    }
+}
+
+/// Classic minesweeper difficulty presets
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// 9x9 map with 10 bombs
+    Beginner,
+    /// 16x16 map with 40 bombs
+    Intermediate,
+    /// 30x16 map with 99 bombs
+    Expert
+}
+
+/// Options of several boards played at once, one per board.
+///
+/// When present as a resource it is used instead of `BoardOptions`,
+/// the board ids matching the options indices
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MultiBoardOptions(pub Vec<BoardOptions>);
+
+impl BoardOptions {
+    /// Options for a classic `difficulty` preset, with a safe start
+    pub fn from_difficulty(difficulty: Difficulty) -> Self {
+        let (map_size, bomb_count) = match difficulty {
+            Difficulty::Beginner => ((9, 9), 10),
+            Difficulty::Intermediate => ((16, 16), 40),
+            Difficulty::Expert => ((30, 16), 99)
+        };
+        Self {
+            map_size,
+            bomb_count,
+            safe_start: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn beginner() -> Self { Self::from_difficulty(Difficulty::Beginner) }
+    pub fn intermediate() -> Self { Self::from_difficulty(Difficulty::Intermediate) }
+    pub fn expert() -> Self { Self::from_difficulty(Difficulty::Expert) }
+
+    /// Checks that the options describe a playable board
+    pub fn validate(&self) -> Result<(), BoardOptionsError> {
+        let (width, height) = self.map_size;
+        if width == 0 || height == 0 {
+            return Err(BoardOptionsError::EmptyMap { map_size: self.map_size });
+        }
+        // At least one tile must stay safe
+        let tile_count = width as u32 * height as u32;
+        if self.bomb_count as u32 >= tile_count {
+            return Err(BoardOptionsError::TooManyBombs {
+                bomb_count: self.bomb_count,
+                tile_count
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Error raised by `BoardOptions::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardOptionsError {
+    /// The map has a zero width or height
+    EmptyMap { map_size: (u16, u16) },
+    /// The bombs don't leave any safe tile
+    TooManyBombs { bomb_count: u16, tile_count: u32 },
+}
+
+impl Display for BoardOptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyMap { map_size: (width, height) } => write!(
+                f, "map size {}x{} has no tiles", width, height
+            ),
+            Self::TooManyBombs { bomb_count, tile_count } => write!(
+                f, "{} bombs don't fit in {} tiles, at least one tile must be safe", bomb_count, tile_count
+            ),
         }
     }
-}
\ No newline at end of file
+}
+
+impl Error for BoardOptionsError {}
\ No newline at end of file
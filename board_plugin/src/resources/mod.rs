@@ -1,13 +1,19 @@
-pub(crate) mod tile;
+pub mod tile;
 
-pub(crate) mod tile_map;
+pub mod tile_map;
 pub mod paused;
 
-pub use board::Board;
+pub use board::{Board, BoardId, LastMove, TileMark};
 mod board;
 
 pub use board_options::*;
 mod board_options;
 
 pub use board_assets::*;
-mod board_assets;
\ No newline at end of file
+mod board_assets;
+
+pub use timer::GameTimer;
+mod timer;
+
+pub use saved_game::SavedGame;
+mod saved_game;
\ No newline at end of file
@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+use crate::{resources::{tile_map::TileMap, BoardId}, components::Coordinates};
+
+/// Snapshot of a game, see `Board::save`.
+///
+/// When inserted as a resource, the next created board with the same id is restored from it
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedGame {
+    pub board: BoardId,
+    pub tile_map: TileMap,
+    pub bombs_placed: bool,
+    pub uncovered_tiles: Vec<Coordinates>,
+    pub marked_tiles: Vec<Coordinates>,
+    pub questioned_tiles: Vec<Coordinates>,
+    pub completed: bool,
+    pub exploded: bool,
+    /// Elapsed time of the game timer
+    pub elapsed: Duration,
+}
@@ -1,6 +1,7 @@
 #[cfg(feature = "debug")]
 use colored::Colorize;
 
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Tile {
     Bomb,
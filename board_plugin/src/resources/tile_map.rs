@@ -1,7 +1,9 @@
-use std::ops::{Deref, DerefMut};
-use rand::{thread_rng, Rng};
+use std::{error::Error, fmt::{self, Display}, ops::{Deref, DerefMut}, str::FromStr};
+use bevy::log;
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-use crate::{resources::tile::Tile, components::Coordinates};
+use crate::{resources::{tile::Tile, Connectivity}, components::Coordinates};
 
 const SQUARE_COORDINATES: [(i8, i8); 8] = [
     (-1, -1), (0, -1), (1, -1),
@@ -9,11 +11,20 @@ const SQUARE_COORDINATES: [(i8, i8); 8] = [
     (-1, 1), (0, 1), (1, 1)
 ];
 
-#[derive(Debug, Clone)]
+const CROSS_COORDINATES: [(i8, i8); 4] = [
+    (0, -1),
+    (-1, 0), (1, 0),
+    (0, 1)
+];
+
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TileMap {
     bomb_count: u16,
     height: u16,
     width: u16,
+    seed: Option<u64>,
+    connectivity: Connectivity,
     map: Vec<Vec<Tile>>
 }
 
@@ -23,7 +34,7 @@ impl TileMap {
             .into_iter()
             .map(|_| (0..width).into_iter().map(|_| Tile::Empty).collect())
             .collect();
-        Self { bomb_count: 0, height, width, map }
+        Self { bomb_count: 0, height, width, seed: None, connectivity: Default::default(), map }
     }
 
     #[cfg(feature = "debug")]
@@ -54,8 +65,25 @@ impl TileMap {
     pub fn height(&self) -> u16 { self.height }
     pub fn bomb_count(&self) -> u16 { self.bomb_count }
 
+    /// Sets the seed used to place the bombs, `None` meaning a random layout
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    /// Sets which tiles are adjacent, recomputing the bomb neighbors
+    pub fn set_connectivity(&mut self, connectivity: Connectivity) {
+        self.connectivity = connectivity;
+        self.set_bomb_neighbors();
+    }
+
+    pub fn connectivity(&self) -> Connectivity { self.connectivity }
+
     pub fn safe_square_at(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
-        SQUARE_COORDINATES
+        let offsets: &'static [(i8, i8)] = match self.connectivity {
+            Connectivity::FourWay => &CROSS_COORDINATES,
+            Connectivity::EightWay => &SQUARE_COORDINATES
+        };
+        offsets
             .iter()
             .copied()
             .map(move |tuple| coordinates + tuple)
@@ -80,24 +108,50 @@ impl TileMap {
     }
 
     pub fn set_bombs(&mut self, bomb_count: u16) {
-        self.bomb_count = bomb_count;
-        let mut remaining_bombs = bomb_count;
-        let mut rng = thread_rng();
+        self.set_bombs_excluding(bomb_count, &[]);
+    }
 
-        // Place bombs
-        while remaining_bombs > 0 {
-            let (x, y) = (
-                rng.gen_range(0..self.width) as usize,
-                rng.gen_range(0..self.height) as usize
+    /// Places `bomb_count` bombs while keeping the `exclude` coordinates safe.
+    ///
+    /// If the board is too small to honor the exclusion zone, the excluded tiles are
+    /// used as a fallback, starting from the last ones of `exclude`
+    pub fn set_bombs_excluding(&mut self, bomb_count: u16, exclude: &[Coordinates]) {
+        // The same seed always yields the same layout for a given map size and bomb count,
+        // apart from the excluded tiles
+        let mut rng = match self.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy()
+        };
+        let mut tiles: Vec<Coordinates> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Coordinates { x, y }))
+            .collect();
+        tiles.shuffle(&mut rng);
+        let (mut candidates, excluded): (Vec<Coordinates>, Vec<Coordinates>) = tiles
+            .into_iter()
+            .partition(|coords| !exclude.contains(coords));
+
+        if candidates.len() < bomb_count as usize {
+            log::warn!(
+                "Not enough tiles to keep {} tiles safe, placing bombs on them",
+                excluded.len()
             );
+            let mut excluded = excluded;
+            excluded.sort_by_key(|coords| std::cmp::Reverse(exclude.iter().position(|c| c == coords)));
+            candidates.extend(excluded);
+        }
 
-            if let Tile::Empty = self[y][x] {
-                self[y][x] = Tile::Bomb;
-                remaining_bombs -= 1;
-            }
+        // Place bombs
+        candidates.truncate(bomb_count as usize);
+        for coords in candidates.iter() {
+            self[coords.y as usize][coords.x as usize] = Tile::Bomb;
         }
+        self.bomb_count = candidates.len() as u16;
+
+        self.set_bomb_neighbors();
+    }
 
-        // Place bomb neighbors
+    /// Computes the bomb neighbor tiles from the current bomb layout
+    fn set_bomb_neighbors(&mut self) {
         for y in 0..self.height {
             for x in 0..self.width {
                 let coords = Coordinates { x, y };
@@ -105,12 +159,90 @@ impl TileMap {
                 if self.is_bomb_at(coords) { continue; }
 
                 let num = self.bomb_count_at(coords);
-                if num == 0 { continue; }
-
                 let tile = &mut self[y as usize][x as usize];
-                *tile = Tile::BombNeighbor(num);
+                *tile = if num == 0 { Tile::Empty } else { Tile::BombNeighbor(num) };
+            }
+        }
+    }
+}
+
+/// Error raised when parsing a `TileMap` from a text layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TileMapParseError {
+    /// The layout has no rows
+    Empty,
+    /// A row length differs from the first row, `row` is the input line starting at 0
+    UnequalRow { row: usize, expected: usize, found: usize },
+    /// A character is neither a bomb (`*`) nor an empty tile (`.` or space), `row` is the input line starting at 0
+    UnknownCharacter { row: usize, column: usize, character: char },
+}
+
+impl Display for TileMapParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the tile map layout is empty"),
+            Self::UnequalRow { row, expected, found } => write!(
+                f, "row {} has {} tiles, expected {}", row, found, expected
+            ),
+            Self::UnknownCharacter { row, column, character } => write!(
+                f, "unknown tile '{}' at row {}, column {}", character, row, column
+            ),
+        }
+    }
+}
+
+impl Error for TileMapParseError {}
+
+impl FromStr for TileMap {
+    type Err = TileMapParseError;
+
+    /// Parses a layout where `*` is a bomb and `.` or a space is an empty tile.
+    ///
+    /// The first line is the top row of the map, like in `console_output`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // Only the blank lines around the layout are ignored, rows keep their input line number
+        let mut rows: Vec<(usize, &str)> = input
+            .lines()
+            .enumerate()
+            .skip_while(|(_, line)| line.is_empty())
+            .collect();
+        while rows.last().is_some_and(|(_, line)| line.is_empty()) {
+            rows.pop();
+        }
+        let width = match rows.first() {
+            None => return Err(TileMapParseError::Empty),
+            Some((_, line)) => line.chars().count()
+        };
+
+        let mut map = Vec::with_capacity(rows.len());
+        for (row, line) in rows.into_iter() {
+            let tiles = line
+                .chars()
+                .enumerate()
+                .map(|(column, character)| match character {
+                    '*' => Ok(Tile::Bomb),
+                    '.' | ' ' => Ok(Tile::Empty),
+                    _ => Err(TileMapParseError::UnknownCharacter { row, column, character })
+                })
+                .collect::<Result<Vec<Tile>, _>>()?;
+            if tiles.len() != width {
+                return Err(TileMapParseError::UnequalRow { row, expected: width, found: tiles.len() });
             }
+            map.push(tiles);
         }
+        map.reverse();
+
+        let bomb_count = map.iter().flatten().filter(|tile| tile.is_bomb()).count();
+        let mut tile_map = Self {
+            bomb_count: bomb_count as u16,
+            height: map.len() as u16,
+            width: width as u16,
+            seed: None,
+            connectivity: Default::default(),
+            map
+        };
+        tile_map.set_bomb_neighbors();
+        Ok(tile_map)
     }
 }
 
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// Elapsed time of a game, see `Board::timer`
+#[derive(Debug, Clone, Default)]
+pub struct GameTimer {
+    elapsed: Duration,
+    finished: bool,
+}
+
+impl GameTimer {
+    /// Resumes a saved game timer
+    pub fn restored(elapsed: Duration, finished: bool) -> Self {
+        Self { elapsed, finished }
+    }
+
+    /// Advances the timer by `delta` unless it is finished
+    pub fn tick(&mut self, delta: Duration) {
+        if !self.finished {
+            self.elapsed += delta;
+        }
+    }
+
+    /// Stops the timer for good
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    pub fn is_finished(&self) -> bool { self.finished }
+    pub fn elapsed(&self) -> Duration { self.elapsed }
+    pub fn elapsed_secs(&self) -> f32 { self.elapsed.as_secs_f32() }
+}
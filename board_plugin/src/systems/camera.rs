@@ -0,0 +1,82 @@
+use bevy::{prelude::*, input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel}, render::camera::CameraPlugin};
+
+use crate::resources::Board;
+
+/// Smallest camera projection scale, the most zoomed in
+pub const MIN_ZOOM: f32 = 0.25;
+/// Biggest camera projection scale, the most zoomed out
+pub const MAX_ZOOM: f32 = 2.0;
+/// Projection scale change for each mouse wheel line
+pub const ZOOM_STEP: f32 = 0.1;
+/// Pixels of touchpad scrolling worth one mouse wheel line
+pub const PIXELS_PER_LINE: f32 = 20.0;
+
+/// Is `camera` the 2D camera showing the boards
+fn is_board_camera(camera: &Camera) -> bool {
+    camera.name.as_deref() == Some(CameraPlugin::CAMERA_2D)
+}
+
+/// Translates a window position to world space through the 2D camera, if any
+pub(crate) fn window_to_world(
+    window: &Window,
+    position: Vec2,
+    cameras: &Query<(&Camera, &Transform, &OrthographicProjection)>,
+) -> Vec2 {
+    let window_size = Vec2::new(window.width(), window.height());
+    let position = position - window_size / 2.0;
+    match cameras.iter().find(|(camera, _, _)| is_board_camera(camera)) {
+        Some((_, transform, projection)) => transform.translation.truncate() + position * projection.scale,
+        None => position
+    }
+}
+
+/// Zooms the 2D camera in and out with the mouse wheel
+pub fn zoom_camera(
+    mut cameras: Query<(&Camera, &mut OrthographicProjection)>,
+    mut wheel_evr: EventReader<MouseWheel>,
+) {
+    // Touchpads scroll by pixels, mouse wheels by lines
+    let delta: f32 = wheel_evr
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        })
+        .sum();
+    if delta == 0.0 { return; }
+    // Each line scales by the same factor, which stays positive whatever the delta
+    let factor = (1.0 - ZOOM_STEP).powf(delta);
+    for (camera, mut projection) in cameras.iter_mut() {
+        if !is_board_camera(camera) { continue; }
+        projection.scale = (projection.scale * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+}
+
+/// Pans the 2D camera while the middle mouse button is held, keeping it over the boards
+pub fn pan_camera(
+    mut cameras: Query<(&Camera, &OrthographicProjection, &mut Transform)>,
+    boards: Query<&Board>,
+    buttons: Res<Input<MouseButton>>,
+    mut motion_evr: EventReader<MouseMotion>,
+) {
+    let delta: Vec2 = motion_evr.iter().map(|event| event.delta).sum();
+    // Left and right buttons are left to the tile input
+    let delta = if buttons.pressed(MouseButton::Middle) { delta } else { Vec2::ZERO };
+
+    // The camera center stays within the area covered by the boards
+    let (min, max) = match boards.iter().map(|b| (b.bounds.position, b.bounds.position + b.bounds.size))
+        .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    {
+        Some(v) => v,
+        None => return
+    };
+
+    for (camera, projection, mut transform) in cameras.iter_mut() {
+        if !is_board_camera(camera) { continue; }
+        // Mouse motion y goes down unlike world y, the content follows the cursor
+        let position = transform.translation.truncate() - delta * Vec2::new(1.0, -1.0) * projection.scale;
+        let position = position.clamp(min, max);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
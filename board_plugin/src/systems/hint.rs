@@ -0,0 +1,106 @@
+use bevy::{prelude::*, log};
+use rand::{seq::SliceRandom, thread_rng};
+
+use crate::{resources::{Board, LastMove, paused::Paused}, events::HintEvent, components::{Uncover, Coordinates}};
+
+pub fn hint_handler(
+    mut commands: Commands,
+    mut boards: Query<&mut Board>,
+    mut hint_evr: EventReader<HintEvent>,
+    paused: Res<Paused>,
+) {
+    if paused.0 { return; }
+    for event in hint_evr.iter() {
+        let mut board = match boards.iter_mut().find(|b| b.id == event.0) {
+            Some(b) => b,
+            None => continue
+        };
+        if board.exploded || board.completed {
+            log::info!("No hint, the game of board {} is over", board.id);
+            continue;
+        }
+        // Bombs are only placed on the first uncover, which is always safe
+        if !board.bombs_placed {
+            log::info!("No hint before the first uncover");
+            continue;
+        }
+        let coords = match hint_tile(&board) {
+            Some(c) => c,
+            None => {
+                log::info!("No safe tile left to hint");
+                continue;
+            }
+        };
+        log::info!("Hint: uncovering tile on {} of board {}", coords, board.id);
+        if let Some(entity) = board.covered_tiles.get(&coords).copied() {
+            board.last_move = LastMove::default();
+            commands.entity(entity).insert(Uncover);
+        }
+    }
+}
+
+/// Picks a covered safe tile the player could not deduce yet, preferably next to the uncovered area.
+///
+/// Only single tile deductions are considered, see `deducible_tiles`. When every safe tile is
+/// deducible one of them is picked anyway
+fn hint_tile(board: &Board) -> Option<Coordinates> {
+    let in_bounds = |c: &Coordinates| c.x < board.tile_map.width() && c.y < board.tile_map.height();
+    let safe_tiles: Vec<Coordinates> = board.covered_tiles
+        .keys()
+        .filter(|c| !board.is_bomb(c) && !board.is_marked(c))
+        .copied()
+        .collect();
+    let deducible = deducible_tiles(board);
+    let hidden: Vec<Coordinates> = safe_tiles
+        .iter()
+        .filter(|c| !deducible.contains(c))
+        .copied()
+        .collect();
+    let candidates = if hidden.is_empty() { safe_tiles } else { hidden };
+    let frontier: Vec<Coordinates> = candidates
+        .iter()
+        .filter(|c| board.tile_map
+            .safe_square_at(**c)
+            .any(|n| in_bounds(&n) && !board.is_covered(&n)))
+        .copied()
+        .collect();
+
+    let mut rng = thread_rng();
+    frontier
+        .choose(&mut rng)
+        .or_else(|| candidates.choose(&mut rng))
+        .copied()
+}
+
+/// Covered safe tiles which follow from the uncovered bomb counters, regardless of the player flags.
+///
+/// A counter with as many covered neighbors as bombs proves they are all bombs, and a counter
+/// with all its bombs proven that way proves its other covered neighbors are safe
+fn deducible_tiles(board: &Board) -> Vec<Coordinates> {
+    let in_bounds = |c: &Coordinates| c.x < board.tile_map.width() && c.y < board.tile_map.height();
+    let counters: Vec<(u8, Vec<Coordinates>)> = (0..board.tile_map.height())
+        .flat_map(|y| (0..board.tile_map.width()).map(move |x| Coordinates { x, y }))
+        .filter(|c| !board.is_covered(c) && !board.is_bomb(c))
+        .map(|c| {
+            let covered = board.tile_map
+                .safe_square_at(c)
+                .filter(|n| in_bounds(n) && board.is_covered(n))
+                .collect();
+            (board.tile_map.bomb_count_at(c), covered)
+        })
+        .filter(|(count, _)| *count > 0)
+        .collect();
+
+    let known_bombs: Vec<Coordinates> = counters
+        .iter()
+        .filter(|(count, covered)| covered.len() == *count as usize)
+        .flat_map(|(_, covered)| covered.iter().copied())
+        .collect();
+    counters
+        .iter()
+        .filter(|(count, covered)| {
+            covered.iter().filter(|c| known_bombs.contains(c)).count() == *count as usize
+        })
+        .flat_map(|(_, covered)| covered.iter().filter(|c| !known_bombs.contains(c)).copied())
+        .collect()
+}
@@ -1,36 +1,144 @@
-use bevy::{prelude::*, input::{mouse::MouseButtonInput, ElementState}, log};
+use bevy::{prelude::*, app::{Events, ManualEventReader}, ecs::system::SystemParam, input::{mouse::MouseButtonInput, touch::{TouchInput, TouchPhase}, ElementState}, log, utils::HashMap};
 
-use crate::{Board, events::{TileTriggerEvent, TileMarkEvent}, resources::paused::Paused};
+use crate::{Board, events::{TileTriggerEvent, TileMarkEvent}, components::Coordinates, resources::{BoardId, paused::Paused}};
+
+use super::camera::window_to_world;
+
+/// Duration in seconds after which a held touch marks its tile
+pub const LONG_PRESS_SECS: f64 = 0.4;
+/// Distance in pixels after which a touch is considered a drag and triggers nothing
+pub const TOUCH_DRAG_DISTANCE: f32 = 20.0;
+
+/// State of an ongoing touch
+#[derive(Debug, Clone, Copy)]
+pub struct TouchStart {
+    position: Vec2,
+    time: f64,
+    /// The touch either marked its tile or moved away, it won't trigger anything else
+    handled: bool,
+}
+
+/// Boards under the cursor and the tile events they receive, shared by the input systems
+#[derive(SystemParam)]
+pub struct BoardInput<'w, 's> {
+    boards: Query<'w, 's, &'static Board>,
+    cameras: Query<'w, 's, (&'static Camera, &'static Transform, &'static OrthographicProjection)>,
+    paused: Res<'w, Paused>,
+    tile_trigger_ewr: EventWriter<'w, 's, TileTriggerEvent>,
+    tile_mark_ewr: EventWriter<'w, 's, TileMarkEvent>,
+}
+
+impl<'w, 's> BoardInput<'w, 's> {
+    /// Finds the board under a window position, along with the matching tile coordinates
+    fn board_at(&self, window: &Window, position: Vec2) -> Option<(BoardId, Coordinates)> {
+        // The camera may be zoomed or panned
+        let position = window_to_world(window, position, &self.cameras);
+        self.boards
+            .iter()
+            .find_map(|board| board.world_position(position).map(|c| (board.id, c)))
+    }
+
+    /// Uncovers the tile under a window position, if any
+    fn trigger(&mut self, window: &Window, position: Vec2) {
+        if let Some((id, coordinates)) = self.board_at(window, position) {
+            log::info!("Trying to uncover tile on {} of board {}", coordinates, id);
+            self.tile_trigger_ewr.send(TileTriggerEvent(id, coordinates));
+        }
+    }
+
+    /// Marks the tile under a window position, if any
+    fn mark(&mut self, window: &Window, position: Vec2) {
+        if let Some((id, coordinates)) = self.board_at(window, position) {
+            log::info!("Trying to mark tile on {} of board {}", coordinates, id);
+            self.tile_mark_ewr.send(TileMarkEvent(id, coordinates));
+        }
+    }
+}
 
 pub fn input_handling(
-    windows: Res<Windows>,
-    board: Res<Board>,
-    mut button_evr: EventReader<MouseButtonInput>,
-    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
-    paused: Res<Paused>,
-    mut tile_mark: EventWriter<TileMarkEvent>,
+    windows: Option<Res<Windows>>,
+    button_events: Option<Res<Events<MouseButtonInput>>>,
+    mut button_evr: Local<ManualEventReader<MouseButtonInput>>,
+    mut input: BoardInput,
 ) {
-    if paused.0 == true { return; }
-    let window = windows.get_primary().unwrap();
+    if input.paused.0 { return; }
+    // Without a window the board is only driven through events
+    let window = windows.as_ref().and_then(|w| w.get_primary());
+    let (window, button_events) = match (window, button_events.as_ref()) {
+        (Some(w), Some(e)) => (w, e),
+        _ => return
+    };
 
-    for event in button_evr.iter() {
+    for event in button_evr.iter(button_events) {
         if let ElementState::Pressed = event.state {
             if let Some(pos) = window.cursor_position() {
                 log::trace!("Mouse button pressed: {:?} at {}", event.button, pos);
-                if let Some(coordinates) = board.mouse_position(window, pos) {
-                    match event.button {
-                        MouseButton::Left => {
-                            log::info!("Trying to uncover tile on {}", coordinates);
-                            tile_trigger_ewr.send(TileTriggerEvent(coordinates));
-                        },
-                        MouseButton::Right => {
-                            log::info!("Trying to mark tile on {}", coordinates);
-                            tile_mark.send(TileMarkEvent(coordinates));
-                        },
-                        _ => ()
+                match event.button {
+                    MouseButton::Left => input.trigger(window, pos),
+                    MouseButton::Right => input.mark(window, pos),
+                    _ => ()
+                }
+            }
+        }
+    }
+}
+
+pub fn touch_handling(
+    windows: Option<Res<Windows>>,
+    time: Res<Time>,
+    touch_events: Option<Res<Events<TouchInput>>>,
+    mut touch_evr: Local<ManualEventReader<TouchInput>>,
+    mut touches: Local<HashMap<u64, TouchStart>>,
+    mut input: BoardInput,
+) {
+    if input.paused.0 {
+        touches.clear();
+        return;
+    }
+    // Without a window the board is only driven through events
+    let window = windows.as_ref().and_then(|w| w.get_primary());
+    let (window, touch_events) = match (window, touch_events.as_ref()) {
+        (Some(w), Some(e)) => (w, e),
+        _ => return
+    };
+    let now = time.seconds_since_startup();
+
+    for event in touch_evr.iter(touch_events) {
+        match event.phase {
+            TouchPhase::Started => {
+                log::trace!("Touch started at {}", event.position);
+                touches.insert(event.id, TouchStart {
+                    position: event.position,
+                    time: now,
+                    handled: false
+                });
+            },
+            TouchPhase::Moved => {
+                if let Some(touch) = touches.get_mut(&event.id) {
+                    if touch.position.distance(event.position) > TOUCH_DRAG_DISTANCE {
+                        touch.handled = true;
                     }
                 }
+            },
+            // A short tap uncovers the tile
+            TouchPhase::Ended => {
+                let touch = match touches.remove(&event.id) {
+                    Some(t) => t,
+                    None => continue
+                };
+                if touch.handled { continue; }
+                input.trigger(window, touch.position);
+            },
+            TouchPhase::Cancelled => {
+                touches.remove(&event.id);
             }
         }
     }
-}
\ No newline at end of file
+
+    // A long press marks the tile
+    for touch in touches.values_mut() {
+        if touch.handled || now - touch.time < LONG_PRESS_SECS { continue; }
+        touch.handled = true;
+        input.mark(window, touch.position);
+    }
+}
@@ -1,42 +1,153 @@
-use bevy::{prelude::*, log};
+use bevy::{prelude::*, ecs::system::SystemParam, log};
 
-use crate::{resources::{Board, BoardAssets}, events::TileMarkEvent};
+use crate::{components::Coordinates, resources::{Board, BoardAssets, LastMove, TileMark, paused::Paused}, events::{TileMarkEvent, TileFlaggedEvent, BoardCompletedEvent}};
+
+/// Tile covers along with their mark sprites
+#[derive(SystemParam)]
+pub struct Covers<'w, 's> {
+    children: Query<'w, 's, &'static Children>,
+    sprites: Query<'w, 's, &'static Sprite>,
+}
+
+impl<'w, 's> Covers<'w, 's> {
+    /// Despawns the mark sprite of the cover `entity`, if any
+    fn clear_mark(&self, commands: &mut Commands, entity: Entity) {
+        if let Ok(children) = self.children.get(entity) {
+            for child in children.iter() {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+    }
+
+    /// The mark matches its cover, which keeps its spawn size when the board is rescaled
+    fn mark_size(&self, entity: Entity, board: &Board) -> Vec2 {
+        self.sprites
+            .get(entity)
+            .ok()
+            .and_then(|sprite| sprite.custom_size)
+            .unwrap_or_else(|| Vec2::splat(board.tile_size))
+    }
+}
 
 pub fn mark_tiles(
     mut commands: Commands,
-    mut board: ResMut<Board>,
+    mut boards: Query<&mut Board>,
     board_assets: Res<BoardAssets>,
     mut tile_mark: EventReader<TileMarkEvent>,
-    query: Query<&Children>,
+    mut flagged: EventWriter<TileFlaggedEvent>,
+    covers: Covers,
+    paused: Res<Paused>,
 ) {
+    if paused.0 { return; }
     for event in tile_mark.iter() {
-        if let Some((entity, mark)) = board.try_toggle_mark(&event.0) {
-            if mark {
-                commands.entity(entity).with_children(|parent| {
-                    parent.spawn_bundle(SpriteBundle {
-                        texture: board_assets.flag_material.texture.clone(),
-                        sprite: Sprite {
-                            custom_size: Some(Vec2::splat(board.tile_size)),
-                            color: board_assets.flag_material.color,
-                            ..Default::default()
-                        },
-                        transform: Transform::from_xyz(0.0, 0.0, 1.0),
-                        ..Default::default()
-                    })
-                          .insert(Name::new("Flag"));
-                });
-            } else {
-                let children = match query.get(entity) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        log::error!("Failed to retrieve flag entity components: {}", e);
-                        continue;
-                    }
-                };
-                for child in children.iter() {
-                    commands.entity(*child).despawn_recursive();
-                }
-            }
+        let mut board = match boards.iter_mut().find(|b| b.id == event.0) {
+            Some(b) => b,
+            None => continue
+        };
+        let previous = board.mark_at(&event.1);
+        let (entity, mark) = match board.try_cycle_mark(&event.1) {
+            None => {
+                log::debug!("Tried to mark an uncovered tile on {}", event.1);
+                continue;
+            },
+            Some(v) => v
+        };
+        board.last_move = LastMove {
+            mark: Some((event.1, previous)),
+            ..Default::default()
+        };
+        if (previous == TileMark::Flagged) != (mark == TileMark::Flagged) {
+            flagged.send(TileFlaggedEvent {
+                board: board.id,
+                coords: event.1,
+                flagged: mark == TileMark::Flagged,
+            });
+        }
+        // We remove the previous mark sprite
+        covers.clear_mark(&mut commands, entity);
+        let size = covers.mark_size(entity, &board);
+        spawn_mark(&mut commands, entity, mark, &board_assets, size);
+    }
+}
+
+pub fn flag_remaining_bombs(
+    mut commands: Commands,
+    mut boards: Query<&mut Board>,
+    board_assets: Res<BoardAssets>,
+    mut completed: EventReader<BoardCompletedEvent>,
+    mut flagged: EventWriter<TileFlaggedEvent>,
+    covers: Covers,
+) {
+    for event in completed.iter() {
+        let mut board = match boards.iter_mut().find(|b| b.id == event.0) {
+            Some(b) => b,
+            None => continue
+        };
+        // Only bombs are left covered on a completed board
+        let bombs: Vec<Coordinates> = board.covered_tiles
+            .keys()
+            .filter(|c| !board.is_marked(c))
+            .copied()
+            .collect();
+        for coords in bombs {
+            let entity = match board.try_set_mark(&coords, TileMark::Flagged) {
+                Some(e) => e,
+                None => continue
+            };
+            // We remove a question mark if any
+            covers.clear_mark(&mut commands, entity);
+            let size = covers.mark_size(entity, &board);
+            spawn_mark(&mut commands, entity, TileMark::Flagged, &board_assets, size);
+            flagged.send(TileFlaggedEvent { board: board.id, coords, flagged: true });
         }
     }
-}
\ No newline at end of file
+}
+
+/// Spawns the sprite matching `mark` as a child of the tile cover `entity`
+pub(crate) fn spawn_mark(
+    commands: &mut Commands,
+    entity: Entity,
+    mark: TileMark,
+    board_assets: &BoardAssets,
+    size: Vec2,
+) {
+    let (material, name) = match mark {
+        TileMark::Flagged => (&board_assets.flag_material, "Flag"),
+        TileMark::Questioned => (&board_assets.question_material, "Question Mark"),
+        TileMark::Unmarked => return,
+    };
+    commands.entity(entity).with_children(|parent| {
+        parent.spawn_bundle(SpriteBundle {
+            texture: material.texture.clone(),
+            sprite: Sprite {
+                custom_size: Some(size),
+                color: material.color,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
+            ..Default::default()
+        })
+              .insert(Name::new(name));
+
+        // An optional `?` is drawn over the question mark sprite, like the bomb counters
+        if let (TileMark::Questioned, Some(color)) = (mark, board_assets.question_mark_color) {
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    "?",
+                    TextStyle {
+                        font: board_assets.bomb_counter_font.clone(),
+                        color,
+                        font_size: size.y,
+                    },
+                    TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center
+                    }
+                ),
+                transform: Transform::from_xyz(0.0, 0.0, 2.0),
+                ..Default::default()
+            })
+                  .insert(Name::new("Question Mark Text"));
+        }
+    });
+}
@@ -1,3 +1,6 @@
 pub mod input;
 pub mod uncover;
 pub mod mark;
+pub mod timer;
+pub mod hint;
+pub mod camera;
\ No newline at end of file
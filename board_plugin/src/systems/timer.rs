@@ -0,0 +1,32 @@
+use bevy::{prelude::*, log};
+
+use crate::{resources::{Board, paused::Paused}, events::{BoardCompletedEvent, BombExplosionEvent}};
+
+pub fn tick_timer(
+    time: Res<Time>,
+    mut boards: Query<&mut Board>,
+    paused: Res<Paused>,
+) {
+    if paused.0 { return; }
+    for mut board in boards.iter_mut() {
+        board.timer.tick(time.delta());
+    }
+}
+
+pub fn stop_timer(
+    mut boards: Query<&mut Board>,
+    mut completed: EventReader<BoardCompletedEvent>,
+    mut explosion: EventReader<BombExplosionEvent>,
+) {
+    let ended = completed.iter().map(|e| e.0).chain(explosion.iter().map(|e| e.0));
+    for id in ended {
+        let mut board = match boards.iter_mut().find(|b| b.id == id) {
+            Some(b) => b,
+            None => continue
+        };
+        // Events left over from a reset board don't match the state of the new one
+        if !(board.exploded || board.completed) || board.timer.is_finished() { continue; }
+        log::info!("Game of board {} ended after {:.1}s", board.id, board.timer.elapsed_secs());
+        board.timer.finish();
+    }
+}
@@ -1,60 +1,156 @@
+use std::collections::VecDeque;
+
 use bevy::{prelude::*, log};
 
-use crate::{resources::{Board, paused::Paused}, events::{TileTriggerEvent, BoardCompletedEvent, BombExplosionEvent}, components::{Uncover, Coordinates, Bomb, BombNeighbor}};
+use crate::{resources::{Board, LastMove, paused::Paused}, events::{TileTriggerEvent, BoardCompletedEvent, BombExplosionEvent, TileUncoveredEvent, TileFlaggedEvent}, components::{Uncover, Coordinates, Bomb, BombNeighbor}};
 
 pub fn trigger_event_handler(
     mut commands: Commands,
-    board: Res<Board>,
+    mut boards: Query<&mut Board>,
     mut tile_trigger_evr: EventReader<TileTriggerEvent>,
     paused: Res<Paused>
 ) {
-    if paused.0 == true { return; }
+    if paused.0 { return; }
     for event in tile_trigger_evr.iter() {
-        if let Some(entity) = board.tile_to_uncover(&event.0) {
-            commands.entity(*entity).insert(Uncover);
+        let mut board = match boards.iter_mut().find(|b| b.id == event.0) {
+            Some(b) => b,
+            None => continue
+        };
+        let entities = match board.tile_to_uncover(&event.1) {
+            Some(entity) => vec![*entity],
+            // Triggering an uncovered tile chords its neighbors
+            None => board.tiles_to_chord(&event.1)
+        };
+        if entities.is_empty() { continue; }
+        // The uncovered tiles, cascade included, make up a new move
+        board.last_move = LastMove::default();
+        for entity in entities {
+            commands.entity(entity).insert(Uncover);
         }
     }
 }
 
 pub fn uncover_tiles(
     mut commands: Commands,
-    mut board: ResMut<Board>,
+    mut boards: Query<&mut Board>,
     children: Query<(Entity, &Parent), With<Uncover>>,
-    parents: Query<(&Coordinates, Option<&Bomb>, Option<&BombNeighbor>)>,
+    parents: Query<(&Coordinates, &Parent, Option<&Bomb>, Option<&BombNeighbor>)>,
     mut completed: EventWriter<BoardCompletedEvent>,
     mut explosion: EventWriter<BombExplosionEvent>,
+    mut uncovered: EventWriter<TileUncoveredEvent>,
 ) {
     // We iterate through tile covers to uncover
     for (entity, parent) in children.iter() {
-        // we destroy the tile cover entity
-        commands.entity(entity)
-                .despawn_recursive();
-
-        let (coords, bomb, bomb_counter) = match parents.get(parent.0) {
+        let (coords, board_entity, bomb, bomb_counter) = match parents.get(parent.0) {
             Ok(v) => v,
             Err(e) => {
                 log::error!("{}", e);
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+        };
+        // Tiles are children of their board entity
+        let mut board = match boards.get_mut(board_entity.0) {
+            Ok(b) => b,
+            Err(e) => {
+                log::error!("Failed to retrieve tile board: {}", e);
+                commands.entity(entity).despawn_recursive();
                 continue;
             }
         };
 
-        match board.try_uncover_tile(coords) {
-            None => log::debug!("Tried to uncover an already uncovered tile"),
-            Some(e) => log::debug!("Uncovered tile {} (entity: {:?})", coords, e),
-        }
-        if board.is_completed() {
-            log::info!("Board completed");
-            completed.send(BoardCompletedEvent);
+        // Flagged tiles are protected from uncovering
+        if board.marked_tiles.contains(coords) {
+            log::debug!("Tried to uncover a flagged tile on {}", coords);
+            commands.entity(entity).remove::<Uncover>();
+            continue;
         }
+
+        uncover_tile(&mut commands, &mut board, *coords, entity, &mut uncovered);
         if bomb.is_some() {
             log::info!("Boom !");
-            explosion.send(BombExplosionEvent);
+            board.exploded = true;
+            explosion.send(BombExplosionEvent(board.id, *coords));
         }
-        // If the tile is empty..
+        // If the tile is empty, we uncover the whole empty area right away
         else if bomb_counter.is_none() {
-            for entity in board.adjacent_covered_tiles(*coords) {
-                commands.entity(entity).insert(Uncover);
+            let mut queue = VecDeque::from([*coords]);
+            while let Some(coords) = queue.pop_front() {
+                let neighbors: Vec<Coordinates> = board.tile_map.safe_square_at(coords).collect();
+                for neighbor in neighbors {
+                    let cover = match board.tile_to_uncover(&neighbor) {
+                        Some(e) => *e,
+                        None => continue
+                    };
+                    uncover_tile(&mut commands, &mut board, neighbor, cover, &mut uncovered);
+                    // Neighbors of an empty tile are never bombs
+                    if board.tile_map.bomb_count_at(neighbor) == 0 {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+    }
+    // We check for completion once all covers of this frame are handled
+    for mut board in boards.iter_mut() {
+        if !board.completed && board.is_completed() {
+            log::info!("Board {} completed", board.id);
+            board.completed = true;
+            completed.send(BoardCompletedEvent(board.id));
+        }
+    }
+}
+
+/// Destroys the `cover` entity of the tile at `coords` and removes it from the board covered tiles
+fn uncover_tile(
+    commands: &mut Commands,
+    board: &mut Board,
+    coords: Coordinates,
+    cover: Entity,
+    uncovered: &mut EventWriter<TileUncoveredEvent>,
+) {
+    commands.entity(cover).despawn_recursive();
+
+    let mark = board.mark_at(&coords);
+    match board.try_uncover_tile(&coords) {
+        None => log::debug!("Tried to uncover an already uncovered tile"),
+        Some(e) => {
+            log::debug!("Uncovered tile {} (entity: {:?})", coords, e);
+            board.last_move.uncovered_tiles.push((coords, mark));
+            uncovered.send(TileUncoveredEvent(board.id, coords));
+        }
+    }
+}
+
+pub fn reveal_bombs(
+    mut commands: Commands,
+    mut boards: Query<&mut Board>,
+    mut explosion: EventReader<BombExplosionEvent>,
+    mut flagged: EventWriter<TileFlaggedEvent>,
+) {
+    for event in explosion.iter() {
+        let mut board = match boards.iter_mut().find(|b| b.id == event.0) {
+            Some(b) => b,
+            None => continue
+        };
+        // The event may be left over from a board that was reset since
+        if !board.exploded { continue; }
+        let bombs: Vec<Coordinates> = board.covered_tiles
+            .keys()
+            .filter(|c| board.tile_map.is_bomb_at(**c))
+            .copied()
+            .collect();
+        log::info!("Revealing {} bombs of board {}", bombs.len(), board.id);
+        // Marks are children of the covers, they are despawned along with them
+        for coords in bombs.iter().filter(|c| board.is_marked(c)) {
+            flagged.send(TileFlaggedEvent { board: board.id, coords: *coords, flagged: false });
+        }
+        board.marked_tiles.retain(|c| !bombs.contains(c));
+        board.questioned_tiles.retain(|c| !bombs.contains(c));
+        for coords in bombs.iter() {
+            if let Some(entity) = board.covered_tiles.remove(coords) {
+                commands.entity(entity).despawn_recursive();
             }
         }
     }
-}
\ No newline at end of file
+}
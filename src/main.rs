@@ -2,7 +2,7 @@ use bevy::{prelude::*, log};
 
 #[cfg(feature="debug")]
 use bevy_inspector_egui::WorldInspectorPlugin;
-use board_plugin::{BoardPlugin, resources::{BoardOptions, paused::Paused, BoardAssets, SpriteMaterial}};
+use board_plugin::{BoardCameraPlugin, BoardPlugin, events::{BoardResetEvent, HintEvent, UndoEvent}, resources::{BoardId, BoardOptions, paused::Paused, BoardAssets, SpriteMaterial}};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum AppState {
@@ -10,9 +10,6 @@ pub enum AppState {
     Out,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct RestartEvent;
-
 fn main() {
     let mut app = App::new();
     app.insert_resource(WindowDescriptor {
@@ -27,7 +24,7 @@ fn main() {
     .add_plugin(BoardPlugin {
         running_state: AppState::InGame,
     })
-    .add_event::<RestartEvent>();
+    .add_plugin(BoardCameraPlugin);
 
     #[cfg(feature="debug")]
     app.add_plugin(WorldInspectorPlugin::new());
@@ -35,7 +32,6 @@ fn main() {
     app.add_startup_system(camera_setup)
        .add_startup_system(setup_board)
        .add_system(state_handler)
-       .add_system(restart)
        .run();
 }
 
@@ -46,7 +42,9 @@ fn camera_setup(mut commands: Commands) {
 fn state_handler(
     mut state: ResMut<State<AppState>>,
     keys: Res<Input<KeyCode>>,
-    mut writer: EventWriter<RestartEvent>,
+    mut reset: EventWriter<BoardResetEvent>,
+    mut hint: EventWriter<HintEvent>,
+    mut undo: EventWriter<UndoEvent>,
     mut paused: ResMut<Paused>
 ) {
     if keys.just_pressed(KeyCode::Escape) {
@@ -60,6 +58,20 @@ fn state_handler(
             paused.0 = false;
         }
     }
+    if keys.just_pressed(KeyCode::H) {
+        log::debug!("pressing `H` detected");
+        if state.current() == &AppState::InGame {
+            log::info!("asking for a hint");
+            hint.send(HintEvent(BoardId::default()));
+        }
+    }
+    if keys.just_pressed(KeyCode::U) {
+        log::debug!("pressing `U` detected");
+        if state.current() == &AppState::InGame {
+            log::info!("undoing last move");
+            undo.send(UndoEvent(BoardId::default()));
+        }
+    }
     if keys.just_pressed(KeyCode::G) {
         log::debug!("pressing `G` detected");
         log::info!("generating new board");
@@ -68,22 +80,12 @@ fn state_handler(
                 state.set(AppState::InGame).unwrap();
             }
             _ => {
-                state.set(AppState::Out).unwrap();
-                writer.send(RestartEvent);
+                reset.send(BoardResetEvent);
             }
         }
     }
 }
 
-fn restart(mut reader: EventReader<RestartEvent>, mut state: ResMut<State<AppState>>) {
-    for _event in reader.iter() {
-        if state.current() == &AppState::Out {
-            state.set(AppState::InGame).unwrap();
-            break;
-        }
-    }
-}
-
 fn setup_board(
     mut commands: Commands,
     mut state: ResMut<State<AppState>>,
@@ -91,11 +93,8 @@ fn setup_board(
 ) {
     // Board plugin options
     commands.insert_resource(BoardOptions {
-        map_size: (20, 20),
-        bomb_count: 40,
         tile_padding: 3.0,
-        safe_start: true,
-        ..Default::default()
+        ..BoardOptions::intermediate()
     });
     // Board assets
     commands.insert_resource(BoardAssets {
@@ -118,6 +117,11 @@ fn setup_board(
             texture: asset_server.load("sprites/flag.png"),
             color: Color::WHITE,
         },
+        question_material: SpriteMaterial {
+            texture: asset_server.load("sprites/flag.png"),
+            color: Color::rgba(1.0, 1.0, 1.0, 0.4),
+        },
+        question_mark_color: Some(Color::BLUE),
         bomb_material: SpriteMaterial {
             texture: asset_server.load("sprites/bomb.png"),
             color: Color::WHITE,